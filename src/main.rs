@@ -10,243 +10,799 @@
 // Do not learn from me, kids
 // I am not a good role model.
 
-use futures::executor::block_on;
-use futures_signals::signal;
-use futures_signals::signal::{Mutable, SignalExt};
 use rdev::EventType::{KeyPress, KeyRelease};
-use rdev::Key::{Alt, AltGr, ControlLeft, ControlRight, Insert, KeyD, KeyE, KeyS, ShiftLeft};
+use rdev::Key::{Alt, ControlLeft, KeyD};
 use rdev::{listen, Event, Key};
-use std::collections::HashMap;
+use std::collections::{HashSet, VecDeque};
 use std::error::Error;
 use std::process::{Command, Output};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::Receiver;
-use std::sync::{mpsc, Arc, Mutex};
+use std::sync::{mpsc, Arc, Condvar, Mutex, RwLock};
 use std::thread;
 use std::thread::JoinHandle;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-struct ScreenState(Arc<AtomicBool>);
-
-impl Clone for ScreenState {
-    fn clone(&self) -> Self {
-        Self(self.0.clone())
-    }
-}
-
-impl PartialEq for ScreenState {
-    fn eq(&self, other: &Self) -> bool {
-        other.0.load(Ordering::SeqCst) == self.0.load(Ordering::SeqCst)
-    }
+// The screen's desired state is a single bool (`true` == on) guarded by a
+// `Mutex` and paired with a `Condvar` so the enforcer thread can *sleep*
+// until something actually changes instead of busy-polling. Transitions are
+// fanned out to subscribers through `broadcast`. Clones share both.
+#[derive(Clone)]
+struct ScreenState {
+    inner: Arc<(Mutex<bool>, Condvar)>,
+    broadcast: Arc<Broadcast>,
 }
 
 impl ScreenState {
     fn new() -> Self {
-        Self(Arc::new(AtomicBool::new(true)))
+        Self {
+            inner: Arc::new((Mutex::new(true), Condvar::new())),
+            broadcast: Arc::new(Broadcast::new()),
+        }
     }
+    // Part of the `ScreenState` API mandated by the spec; not every accessor
+    // has a caller yet, so the unused ones are allowed rather than deleted.
+    #[allow(dead_code)]
     fn is_off(&self) -> bool {
-        !self.0.load(Ordering::SeqCst)
+        !*self.inner.0.lock().unwrap()
     }
+    #[allow(dead_code)]
     fn is_on(&self) -> bool {
-        self.0.load(Ordering::SeqCst)
+        *self.inner.0.lock().unwrap()
     }
 
-    fn set_on(&mut self) {
-        self.0.store(true, Ordering::SeqCst);
+    fn set_on(&self) {
+        let (lock, cvar) = &*self.inner;
+        let changed = {
+            let mut on = lock.lock().unwrap();
+            let was = *on;
+            *on = true;
+            !was
+        };
+        cvar.notify_all();
+        if changed {
+            self.broadcast.publish(ScreenEvent::TurnedOn);
+        }
     }
 
-    fn set_off(&mut self) {
-        self.0.store(false, Ordering::SeqCst);
+    #[allow(dead_code)]
+    fn set_off(&self) {
+        let (lock, cvar) = &*self.inner;
+        let changed = {
+            let mut on = lock.lock().unwrap();
+            let was = *on;
+            *on = false;
+            was
+        };
+        cvar.notify_all();
+        if changed {
+            self.broadcast.publish(ScreenEvent::TurnedOff);
+        }
     }
-    fn set_from(&mut self, other: &Self) {
-        self.0
-            .store(other.0.load(Ordering::SeqCst), Ordering::SeqCst);
+
+    fn toggle(&self) {
+        let (lock, cvar) = &*self.inner;
+        let now_on = {
+            let mut on = lock.lock().unwrap();
+            *on = !*on;
+            *on
+        };
+        cvar.notify_all();
+        self.broadcast.publish(if now_on {
+            ScreenEvent::TurnedOn
+        } else {
+            ScreenEvent::TurnedOff
+        });
     }
-    fn toggle(&mut self) {
-        let current_state = self.0.load(Ordering::SeqCst);
-        self.0.store(!current_state, Ordering::SeqCst);
+}
+
+/// A screen-state transition, broadcast to every subscriber.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum ScreenEvent {
+    TurnedOn,
+    TurnedOff,
+}
+
+// A minimal broadcast channel: one producer, many independent receivers, each
+// seeing every message. A slow receiver drops its oldest buffered events
+// rather than blocking the producer — the same drop-oldest contract tokio's
+// `broadcast` offers.
+struct Broadcast {
+    subscribers: Mutex<Vec<Arc<Subscriber>>>,
+}
+
+struct Subscriber {
+    queue: Mutex<VecDeque<ScreenEvent>>,
+    cvar: Condvar,
+    capacity: usize,
+}
+
+/// The receiving half handed to each subscriber.
+struct BroadcastReceiver(Arc<Subscriber>);
+
+impl BroadcastReceiver {
+    /// Block until the next event is available.
+    fn recv(&self) -> ScreenEvent {
+        let mut queue = self.0.queue.lock().unwrap();
+        loop {
+            if let Some(event) = queue.pop_front() {
+                return event;
+            }
+            queue = self.0.cvar.wait(queue).unwrap();
+        }
     }
 }
 
+impl Broadcast {
+    fn new() -> Self {
+        Self {
+            subscribers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Register a new subscriber buffering up to `capacity` unread events.
+    fn subscribe(&self, capacity: usize) -> BroadcastReceiver {
+        let sub = Arc::new(Subscriber {
+            queue: Mutex::new(VecDeque::new()),
+            cvar: Condvar::new(),
+            capacity,
+        });
+        self.subscribers.lock().unwrap().push(sub.clone());
+        BroadcastReceiver(sub)
+    }
+
+    /// Fan `event` out to every subscriber, dropping the oldest queued event
+    /// for any receiver that's fallen behind.
+    fn publish(&self, event: ScreenEvent) {
+        for sub in self.subscribers.lock().unwrap().iter() {
+            let mut queue = sub.queue.lock().unwrap();
+            while queue.len() >= sub.capacity {
+                queue.pop_front();
+            }
+            queue.push_back(event);
+            sub.cvar.notify_one();
+        }
+    }
+}
+
+/// Subscriber: timestamp every transition to stdout.
+fn spawn_logger(rx: BroadcastReceiver) -> JoinHandle<()> {
+    thread::spawn(move || loop {
+        let event = rx.recv();
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        println!("[{}] screen {:?}", secs, event);
+    })
+}
+
+/// Where the status subscriber records the current forced state so external
+/// tools can ask "is the screen currently forced off?" without IPC ceremony.
+static STATUS_PATH: &str = "/tmp/screen-toggle.status";
+
+/// Subscriber: mirror the current state into a file.
+fn spawn_status_writer(rx: BroadcastReceiver) -> JoinHandle<()> {
+    let write = |text: &str| {
+        if let Err(error) = std::fs::write(STATUS_PATH, text) {
+            eprintln!("Failed to write status file: {:?}", error);
+        }
+    };
+    thread::spawn(move || {
+        // Seed the file with the startup state so external tools have something
+        // to read before the first transition. `ScreenState` starts on.
+        write("on");
+        loop {
+            let text = match rx.recv() {
+                ScreenEvent::TurnedOn => "on",
+                ScreenEvent::TurnedOff => "off",
+            };
+            write(text);
+        }
+    })
+}
+
 struct ScreenStateEnforcer {
     state: ScreenState,
-    old_state: ScreenState,
+    // The re-assert cadence this enforcer was started with. The live value is
+    // read from `Config` each loop so a reload can retune it; this field keeps
+    // the startup value for introspection.
+    #[allow(dead_code)]
+    reassert_interval: Duration,
     update: JoinHandle<()>,
 }
 
 impl ScreenStateEnforcer {
-    fn new() -> Self {
-        let state = ScreenState::new();
-        let old_state = ScreenState::new();
+    fn new(
+        state: ScreenState,
+        token: CancellationToken,
+        config: Arc<RwLock<Config>>,
+    ) -> Self {
+        // How often we re-assert "off" while the screen is forced off, tunable
+        // via `Config` (and thus at runtime through a reload).
+        let reassert_interval =
+            Duration::from_millis(config.read().unwrap().reassert_interval_ms);
 
         let state_ptr = state.clone();
-        let mut old_state_ptr = old_state.clone();
-        let update = thread::spawn(move || loop {
-            while state_ptr.is_off() {
-                // Now we turn off the screens over and over EVERY 100 MS
-                // faster than whatever's turning them on can act
-                // Yes, this is horrifying.
-                // Yes, I don't care.
-                // Fuck off.
-                Self::send_off_cmd().expect("Could not send.");
-                thread::sleep(Duration::from_millis(50));
-            }
-            // only reachable once state_ptr becomes on, we we assume that
-            // Debounce against previous state so we only send if state has changed
-            if state_ptr != old_state_ptr {
-                // Send this command 100 times because I don't trust anyone else's code but my own
-                for _ in 0..100 {
-                    // Send command to turn screen on
-                    Self::send_on_cmd().expect("Could not send.");
-                    thread::sleep(Duration::from_millis(100));
+        let token_ptr = token;
+        let config_ptr = config;
+        let update = thread::spawn(move || {
+            let (lock, cvar) = &*state_ptr.inner;
+            loop {
+                if token_ptr.is_cancelled() {
+                    break;
+                }
+                let on = lock.lock().unwrap();
+                // Re-check under the lock: `cancel` sets the flag before it
+                // takes this lock to notify, so a cancel can't slip in unseen
+                // between the check above and the wait below.
+                if token_ptr.is_cancelled() {
+                    break;
+                }
+                if *on {
+                    // Screen should be on: block completely until someone
+                    // flips the bool and wakes us. No timeout, no spinning.
+                    // A spurious wakeup (or a cancel notify) just re-checks
+                    // the flags next loop.
+                    let _on = cvar.wait(on).unwrap();
+                } else {
+                    // Screen should be off: wake on the timeout and re-assert,
+                    // faster than whatever keeps turning it back on. Re-read the
+                    // interval each time so a reload takes effect. We drop the
+                    // guard before shelling out so toggles aren't blocked by the
+                    // command.
+                    let interval = Duration::from_millis(
+                        config_ptr.read().unwrap().reassert_interval_ms,
+                    );
+                    let (guard, timeout) = cvar.wait_timeout(on, interval).unwrap();
+                    let still_off = !*guard;
+                    drop(guard);
+                    if token_ptr.is_cancelled() {
+                        break;
+                    }
+                    if timeout.timed_out() && still_off {
+                        // Fight GNOME, one re-assert at a time.
+                        Self::send_off_cmd(&config_ptr).expect("Could not send.");
+                    }
                 }
-                old_state_ptr.set_from(&state_ptr);
             }
-            thread::sleep(Duration::from_millis(50));
         });
 
         Self {
             state,
-            old_state,
+            reassert_interval,
             update,
         }
     }
 
-    /// Send command to turn screen off
-    fn send_off_cmd() -> Result<Output, Box<dyn Error>> {
-        println!("send_off_cmd()");
-        Ok(Command::new("xset")
-            .arg("dpms")
-            .arg("force")
-            .arg("off")
-            .spawn()?
-            .wait_with_output()?)
+    /// Send command to turn screen off, using the configured template.
+    fn send_off_cmd(config: &Arc<RwLock<Config>>) -> Result<Output, Box<dyn Error>> {
+        // Snapshot the template under a cheap read lock, then drop it before
+        // shelling out so a reload isn't blocked by the command.
+        let template = config.read().unwrap().off_cmd.clone();
+        run_command(&template)
     }
 
-    /// Send command to turn screen on
-    fn send_on_cmd() -> Result<Output, Box<dyn Error>> {
-        println!("send_on_cmd()");
-        Ok(Command::new("xset")
-            .arg("dpms")
-            .arg("force")
-            .arg("on")
-            .spawn()?
-            .wait_with_output()?)
+    /// Send command to turn screen on, using the configured template.
+    fn send_on_cmd(config: &Arc<RwLock<Config>>) -> Result<Output, Box<dyn Error>> {
+        let template = config.read().unwrap().on_cmd.clone();
+        run_command(&template)
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
-enum KeyState {
-    Pressed,
-    Released,
+/// Run a `[program, args..]` command template to completion.
+fn run_command(template: &[String]) -> Result<Output, Box<dyn Error>> {
+    let (program, args) = template
+        .split_first()
+        .ok_or("empty command template")?;
+    println!("run: {}", template.join(" "));
+    Ok(Command::new(program)
+        .args(args)
+        .spawn()?
+        .wait_with_output()?)
 }
 
-#[derive(Debug)]
-struct KeyStates(Arc<Mutex<HashMap<Key, KeyState>>>);
-
-impl Clone for KeyStates {
-    fn clone(&self) -> Self {
-        Self(self.0.clone())
-    }
+// A tiny cancellation token, modeled on tokio's: a shared flag plus a handle
+// to the enforcer's `Condvar` so cancelling can wake a thread that's parked in
+// `wait`/`wait_timeout`. Clones share the same flag.
+#[derive(Clone)]
+struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    state: ScreenState,
 }
 
-// BTW Wrapping Arc<Mutex<>> like this is probably a bad idea for any _real_ API
-// As it would probably be easy for the API-user to end up with deadlocks and be very confused ;)
-
-impl KeyStates {
-    fn new() -> Self {
-        let mut map = HashMap::new();
-        Self(Arc::new(Mutex::new(map)))
+impl CancellationToken {
+    fn new(state: ScreenState) -> Self {
+        Self {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            state,
+        }
     }
 
-    fn get_state(&self, key: Key) -> KeyState {
-        *self
-            .0
-            .lock()
-            .unwrap() // ah yes, 500 unwrap() in your codebase
-            .entry(key) // truly masterful programming quality
-            .or_insert(KeyState::Released)
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
     }
 
-    fn set_state(&self, key: &Key, state: KeyState) {
-        *self.0.lock().unwrap().entry(*key).or_insert(state) = state;
+    /// Request shutdown. Idempotent: only the first caller does the real work.
+    fn cancel(&self) {
+        if self.cancelled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        // Flip state back on: notifies the enforcer so it wakes and drops out
+        // of its wait, and publishes `TurnedOn` so the status file and loggers
+        // stay consistent. The physical `send_on_cmd` restore is performed by
+        // `main` after the enforcer is joined, so it can't race `process::exit`
+        // from a detached handler thread.
+        self.state.set_on();
     }
 }
 
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum KeyState {
+    Pressed,
+    Released,
+}
+
+/// A single typed keyboard event, stamped with when it happened. These flow
+/// from the `rdev` callback down an `mpsc` channel to whoever's driving policy.
+#[derive(Copy, Clone, Debug)]
+struct KeyEvent {
+    key: Key,
+    state: KeyState,
+    time: Instant,
+}
+
 struct KeyboardState {
-    update: JoinHandle<()>,
-    states: KeyStates,
-    //rx: Receiver<()>,
-    updated: Mutable<bool>,
+    rx: Receiver<KeyEvent>,
 }
 
 impl KeyboardState {
     fn new() -> Self {
-        let updated = Mutable::new(false);
-        let updated_ptr = updated.clone();
-        // let (tx, rx) = mpsc::channel::<()>();
-        let states = KeyStates::new();
-        let states_ptr = states.clone();
+        let (tx, rx) = mpsc::channel::<KeyEvent>();
         let callback = move |event: Event| {
-            updated_ptr.set(true);
-            // tx.send(()).expect("Couldn't send notice of new event");
-
-            match event {
-                Event {
-                    time: _,
-                    name: _,
-                    event_type,
-                } => match event_type {
-                    KeyPress(key) => states_ptr.set_state(&key, KeyState::Pressed),
-                    KeyRelease(key) => states_ptr.set_state(&key, KeyState::Released),
-                    _ => {}
-                },
-            }
+            let (key, state) = match event.event_type {
+                KeyPress(key) => (key, KeyState::Pressed),
+                KeyRelease(key) => (key, KeyState::Released),
+                _ => return,
+            };
+            // If the consumer has hung up we've got nothing left to do.
+            let _ = tx.send(KeyEvent {
+                key,
+                state,
+                time: Instant::now(),
+            });
         };
 
-        let update = thread::spawn(move || {
+        // `rdev::listen` blocks forever and can't be cancelled, so the handle
+        // is detached — there's nothing meaningful to join.
+        thread::spawn(move || {
             if let Err(error) = listen(callback) {
                 eprintln!("Error: {:?}", error)
             }
         });
 
+        Self { rx }
+    }
+}
+
+/// An (unordered) set of keys that fires once they're all held together.
+#[derive(Clone, Debug)]
+struct Chord {
+    keys: HashSet<Key>,
+}
+
+impl Chord {
+    fn new(keys: impl IntoIterator<Item = Key>) -> Self {
         Self {
-            update,
-            states,
-            updated,
+            keys: keys.into_iter().collect(),
         }
     }
+}
 
-    fn wait_until_next(&self) {
-        block_on(self.updated.signal().wait_for(true));
-        self.updated.set(false);
-        //self.rx.recv().expect("Sender hung up :c");
+/// Which registered chord just fired.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Triggered {
+    chord: usize,
+}
+
+/// Tracks the live pressed-set from the event stream and emits `Triggered` the
+/// instant a chord becomes fully satisfied. The debounce lives here now, so a
+/// chord can't re-fire until `debounce` has elapsed since its last trigger.
+/// The chord set is read from the shared `Config` on every event so a reload
+/// takes effect immediately.
+struct ChordDetector {
+    config: Arc<RwLock<Config>>,
+    pressed: HashSet<Key>,
+    last_trigger: Vec<Option<Instant>>,
+    debounce: Duration,
+}
+
+impl ChordDetector {
+    fn new(config: Arc<RwLock<Config>>, debounce: Duration) -> Self {
+        Self {
+            config,
+            pressed: HashSet::new(),
+            last_trigger: Vec::new(),
+            debounce,
+        }
+    }
+
+    /// Feed one event. Returns `Some(Triggered)` only on the edge where a chord
+    /// goes from not-satisfied to satisfied (and isn't still debouncing).
+    fn feed(&mut self, event: &KeyEvent) -> Option<Triggered> {
+        let config = self.config.read().unwrap();
+        let chords = &config.chords;
+        // Keep the debounce bookkeeping in step with the (possibly reloaded)
+        // chord list.
+        if self.last_trigger.len() != chords.len() {
+            self.last_trigger.resize(chords.len(), None);
+        }
+
+        let was: Vec<bool> = chords
+            .iter()
+            .map(|c| c.keys.is_subset(&self.pressed))
+            .collect();
+
+        match event.state {
+            KeyState::Pressed => {
+                self.pressed.insert(event.key);
+            }
+            KeyState::Released => {
+                self.pressed.remove(&event.key);
+            }
+        }
+
+        for i in 0..chords.len() {
+            let now_satisfied = chords[i].keys.is_subset(&self.pressed);
+            if now_satisfied && !was[i] {
+                let ready = match self.last_trigger[i] {
+                    Some(t) => event.time.duration_since(t) > self.debounce,
+                    None => true,
+                };
+                if ready {
+                    self.last_trigger[i] = Some(event.time);
+                    return Some(Triggered { chord: i });
+                }
+            }
+        }
+        None
     }
 }
 
-static DEBOUNCE_MS: u128 = 500;
+static DEBOUNCE_MS: u64 = 500;
 
-fn main() {
-    let kb = KeyboardState::new();
-    let mut ssenforcer = ScreenStateEnforcer::new();
-    let mut time_since_last_toggle = Instant::now();
-    loop {
-        kb.wait_until_next();
-        match (
-            kb.states.get_state(ControlLeft),
-            kb.states.get_state(Alt),
-            kb.states.get_state(KeyD),
-        ) {
-            (KeyState::Pressed, KeyState::Pressed, KeyState::Pressed) => {
-                if time_since_last_toggle.elapsed().as_millis() > DEBOUNCE_MS {
-                    println!("Toggle");
-                    ssenforcer.state.toggle();
-                    time_since_last_toggle = Instant::now();
+/// Runtime-reloadable configuration: which chord(s) toggle the screen and the
+/// backend command templates used to force it off/on. Stored behind an
+/// `RwLock` so the hot path takes cheap read locks while a reload replaces the
+/// whole struct under a single write lock — readers never see a torn config.
+struct Config {
+    chords: Vec<Chord>,
+    off_cmd: Vec<String>,
+    on_cmd: Vec<String>,
+    reassert_interval_ms: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            chords: vec![Chord::new([ControlLeft, Alt, KeyD])],
+            off_cmd: ["xset", "dpms", "force", "off"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            on_cmd: ["xset", "dpms", "force", "on"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            reassert_interval_ms: 50,
+        }
+    }
+}
+
+impl Config {
+    /// Parse a config file. The format is one `key = value` per line, `#` for
+    /// comments:
+    ///
+    /// ```text
+    /// off   = xset dpms force off
+    /// on    = xset dpms force on
+    /// chord = ControlLeft Alt KeyD
+    /// ```
+    ///
+    /// `chord` may appear more than once to register multiple toggles. The
+    /// command lines are whitespace-split into a `[program, args..]` template,
+    /// so switching backend is just `off = wlr-randr --output ... --off`.
+    /// `reassert` sets the off re-assert cadence in milliseconds.
+    fn from_file(path: &str) -> Result<Self, Box<dyn Error>> {
+        Config::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// The `from_file` body, split out so it's exercisable without touching
+    /// the filesystem.
+    fn parse(text: &str) -> Result<Self, Box<dyn Error>> {
+        let mut config = Config::default();
+        let mut chords = Vec::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or("config line missing '='")?;
+            let value = value.trim();
+            match key.trim() {
+                "off" => config.off_cmd = value.split_whitespace().map(str::to_string).collect(),
+                "on" => config.on_cmd = value.split_whitespace().map(str::to_string).collect(),
+                "reassert" => config.reassert_interval_ms = value.parse()?,
+                "chord" => {
+                    let keys = value
+                        .split_whitespace()
+                        .map(parse_key)
+                        .collect::<Option<Vec<Key>>>()
+                        .ok_or("unknown key in chord")?;
+                    chords.push(Chord::new(keys));
+                }
+                other => return Err(format!("unknown config key: {}", other).into()),
+            }
+        }
+        if !chords.is_empty() {
+            config.chords = chords;
+        }
+        Ok(config)
+    }
+}
+
+/// Map an `rdev::Key` variant name (as written in the config) to the key. Only
+/// the variants you'd plausibly bind a toggle to are recognized.
+fn parse_key(name: &str) -> Option<Key> {
+    use rdev::Key::*;
+    Some(match name {
+        "ControlLeft" => ControlLeft,
+        "ControlRight" => ControlRight,
+        "Alt" => Alt,
+        "AltGr" => AltGr,
+        "ShiftLeft" => ShiftLeft,
+        "ShiftRight" => ShiftRight,
+        "MetaLeft" => MetaLeft,
+        "MetaRight" => MetaRight,
+        "KeyA" => KeyA,
+        "KeyB" => KeyB,
+        "KeyC" => KeyC,
+        "KeyD" => KeyD,
+        "KeyE" => KeyE,
+        "KeyF" => KeyF,
+        "KeyG" => KeyG,
+        "KeyH" => KeyH,
+        "KeyI" => KeyI,
+        "KeyJ" => KeyJ,
+        "KeyK" => KeyK,
+        "KeyL" => KeyL,
+        "KeyM" => KeyM,
+        "KeyN" => KeyN,
+        "KeyO" => KeyO,
+        "KeyP" => KeyP,
+        "KeyQ" => KeyQ,
+        "KeyR" => KeyR,
+        "KeyS" => KeyS,
+        "KeyT" => KeyT,
+        "KeyU" => KeyU,
+        "KeyV" => KeyV,
+        "KeyW" => KeyW,
+        "KeyX" => KeyX,
+        "KeyY" => KeyY,
+        "KeyZ" => KeyZ,
+        _ => return None,
+    })
+}
+
+/// The config file path: `$SCREEN_TOGGLE_CONFIG`, else a sensible default.
+fn config_path() -> String {
+    std::env::var("SCREEN_TOGGLE_CONFIG")
+        .unwrap_or_else(|_| "/etc/screen-toggle.conf".to_string())
+}
+
+/// Load the config from disk, falling back to defaults (and saying so) if the
+/// file is missing or malformed.
+fn load_config(path: &str) -> Config {
+    match Config::from_file(path) {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("Using default config ({}: {:?})", path, error);
+            Config::default()
+        }
+    }
+}
+
+/// Watch the config file and hot-swap its contents under the write lock when it
+/// changes, polling its modification time once a second. SIGHUP does not
+/// reload: the `ctrlc` handler in `main` treats it as a shutdown signal.
+fn spawn_config_reloader(
+    path: String,
+    config: Arc<RwLock<Config>>,
+    token: CancellationToken,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let modified = || std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        let mut last_seen = modified();
+        while !token.is_cancelled() {
+            thread::sleep(Duration::from_secs(1));
+            let current = modified();
+            if current == last_seen {
+                continue;
+            }
+            last_seen = current;
+            match Config::from_file(&path) {
+                // Replace the whole struct atomically under the write lock.
+                Ok(new_config) => {
+                    *config.write().unwrap() = new_config;
+                    println!("Reloaded config from {}", path);
                 }
+                Err(error) => eprintln!("Config reload failed: {:?}", error),
             }
-            _ => {}
         }
-        thread::sleep(Duration::from_millis(10));
+    })
+}
+
+fn main() {
+    let kb = KeyboardState::new();
+    let state = ScreenState::new();
+
+    // Fan transitions out to a couple of independent subscribers before the
+    // enforcer starts flipping state. Both buffer up to 64 events, dropping the
+    // oldest if they lag.
+    let _logger = spawn_logger(state.broadcast.subscribe(64));
+    let _status = spawn_status_writer(state.broadcast.subscribe(64));
+
+    // Load config up front and keep it behind an RwLock so the hot path reads
+    // it cheaply while the reloader swaps it on file change.
+    let path = config_path();
+    let config = Arc::new(RwLock::new(load_config(&path)));
+
+    let token = CancellationToken::new(state.clone());
+    let ssenforcer = ScreenStateEnforcer::new(state, token.clone(), config.clone());
+
+    // Route termination signals through the cancel path so a stopped process
+    // always restores the screen instead of leaving the monitor dark. With the
+    // `ctrlc` crate's `termination` feature enabled this covers SIGINT, SIGTERM
+    // (the usual `kill` / service-manager stop) and SIGHUP.
+    {
+        let token = token.clone();
+        ctrlc::set_handler(move || token.cancel())
+            .expect("Could not install signal handler");
+    }
+
+    let _reloader = spawn_config_reloader(path, config.clone(), token.clone());
+
+    let mut detector = ChordDetector::new(config.clone(), Duration::from_millis(DEBOUNCE_MS));
+    while !token.is_cancelled() {
+        // Consume events as they arrive. The timeout lets us re-check the
+        // cancel flag promptly instead of blocking on `recv` forever — the
+        // `rdev::listen` sender never hangs up, so a plain `recv` would wedge
+        // shutdown until the next keypress.
+        let event = match kb.rx.recv_timeout(Duration::from_millis(200)) {
+            Ok(event) => event,
+            Err(mpsc::RecvTimeoutError::Timeout) => continue,
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
+        if token.is_cancelled() {
+            break;
+        }
+        if let Some(Triggered { chord: _ }) = detector.feed(&event) {
+            println!("Toggle");
+            ssenforcer.state.toggle();
+        }
+    }
+
+    // Join the enforcer so it's fully wound down, then perform the physical
+    // restore here — synchronously, before we exit — rather than in the signal
+    // handler thread, which could otherwise lose a race with `process::exit`.
+    let _ = ssenforcer.update.join();
+    if let Err(error) = ScreenStateEnforcer::send_on_cmd(&config) {
+        eprintln!("Failed to restore screen on shutdown: {:?}", error);
+    }
+    // `rdev::listen` can't be interrupted, so its thread can't be joined
+    // without hanging; exit now that the screen is safely restored.
+    std::process::exit(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdev::Key::{KeyA, KeyB, KeyC};
+
+    fn press(key: Key, time: Instant) -> KeyEvent {
+        KeyEvent {
+            key,
+            state: KeyState::Pressed,
+            time,
+        }
+    }
+
+    fn release(key: Key, time: Instant) -> KeyEvent {
+        KeyEvent {
+            key,
+            state: KeyState::Released,
+            time,
+        }
+    }
+
+    fn detector(debounce: Duration) -> ChordDetector {
+        let config = Arc::new(RwLock::new(Config {
+            chords: vec![Chord::new([KeyA, KeyB])],
+            ..Config::default()
+        }));
+        ChordDetector::new(config, debounce)
+    }
+
+    #[test]
+    fn fires_once_when_the_full_chord_is_held() {
+        let mut det = detector(Duration::from_millis(500));
+        let t0 = Instant::now();
+        // Partial press: nothing yet.
+        assert_eq!(det.feed(&press(KeyA, t0)), None);
+        // Completing the chord fires exactly on the satisfying edge.
+        assert_eq!(det.feed(&press(KeyB, t0)), Some(Triggered { chord: 0 }));
+        // Holding and pressing an unrelated key must not re-fire.
+        assert_eq!(det.feed(&press(KeyC, t0)), None);
+    }
+
+    #[test]
+    fn re_press_is_suppressed_until_the_debounce_window_passes() {
+        let mut det = detector(Duration::from_millis(500));
+        let t0 = Instant::now();
+        assert_eq!(det.feed(&press(KeyA, t0)), None);
+        assert_eq!(det.feed(&press(KeyB, t0)), Some(Triggered { chord: 0 }));
+
+        // Release, then re-satisfy within the debounce window: suppressed.
+        det.feed(&release(KeyA, t0));
+        det.feed(&release(KeyB, t0));
+        let t1 = t0 + Duration::from_millis(100);
+        assert_eq!(det.feed(&press(KeyA, t1)), None);
+        assert_eq!(det.feed(&press(KeyB, t1)), None);
+
+        // Release and re-satisfy past the window: fires again.
+        det.feed(&release(KeyA, t1));
+        det.feed(&release(KeyB, t1));
+        let t2 = t0 + Duration::from_millis(600);
+        assert_eq!(det.feed(&press(KeyA, t2)), None);
+        assert_eq!(det.feed(&press(KeyB, t2)), Some(Triggered { chord: 0 }));
+    }
+
+    #[test]
+    fn parses_commands_and_multiple_chords() {
+        let config = Config::parse(
+            "# a comment\n\
+             off   = xset dpms force off\n\
+             on    = xset dpms force on\n\
+             reassert = 120\n\
+             chord = ControlLeft Alt KeyD\n\
+             chord = ControlLeft KeyS\n",
+        )
+        .unwrap();
+        assert_eq!(config.off_cmd, ["xset", "dpms", "force", "off"]);
+        assert_eq!(config.on_cmd, ["xset", "dpms", "force", "on"]);
+        assert_eq!(config.reassert_interval_ms, 120);
+        assert_eq!(config.chords.len(), 2);
+    }
+
+    #[test]
+    fn blank_input_keeps_defaults() {
+        let config = Config::parse("# nothing here\n\n").unwrap();
+        assert_eq!(config.chords.len(), 1);
+        assert_eq!(config.reassert_interval_ms, 50);
+    }
+
+    #[test]
+    fn rejects_unknown_key_in_chord() {
+        assert!(Config::parse("chord = ControlLeft NotAKey").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_config_key() {
+        assert!(Config::parse("wat = 1").is_err());
+    }
+
+    #[test]
+    fn rejects_line_without_equals() {
+        assert!(Config::parse("chord ControlLeft KeyD").is_err());
     }
 }